@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use tts_core::structs::{Result, TTSMode};
+
+// Bump this and add a migration arm in `migrate` whenever a `ProviderDescriptor`
+// field is added, renamed, or changes meaning, so operators' existing config
+// files keep loading instead of failing to parse.
+const CURRENT_VERSION: u32 = 1;
+
+// How the provider's API key is attached to outgoing requests, e.g.
+// `{"header": "Authorization", "scheme": "Bearer"}`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuthConfig {
+    pub header: String,
+    #[serde(default)]
+    pub scheme: Option<String>,
+}
+
+impl AuthConfig {
+    pub fn header_value(&self, auth_key: &str) -> String {
+        match &self.scheme {
+            Some(scheme) => format!("{scheme} {auth_key}"),
+            None => auth_key.to_owned(),
+        }
+    }
+}
+
+// Everything `fetch_voices`/`fetch_translation_languages` need to know about
+// a single TTS backend: previously hardcoded as `set_path("voices")`, the
+// `mode`/`raw` query pairs, and a bare `Authorization` header in `startup.rs`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProviderDescriptor {
+    pub base_url: reqwest::Url,
+    // Used as a retry target if a voices or translation-languages request
+    // against `base_url` errors out; audio synthesis requests do not retry.
+    #[serde(default)]
+    pub fallback_base_url: Option<reqwest::Url>,
+    pub voices_path: String,
+    pub translation_languages_path: Option<String>,
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+    pub auth: AuthConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProviderRegistry {
+    pub version: u32,
+    pub providers: BTreeMap<TTSMode, ProviderDescriptor>,
+}
+
+impl ProviderRegistry {
+    // Parses a provider registry from its on-disk JSON form, migrating it to
+    // `CURRENT_VERSION` first so configs written against an older schema
+    // still load.
+    pub fn from_json(raw: &str) -> Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(raw)?;
+        let from_version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0) as u32;
+
+        migrate(&mut value, from_version);
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+// Upgrades a raw registry document (as parsed JSON) in place to
+// `CURRENT_VERSION`.
+fn migrate(value: &mut serde_json::Value, from_version: u32) {
+    if from_version < 1 {
+        // Unversioned configs predate `auth.scheme` and sent the key bare
+        // (old `fetch_json` did `.header("Authorization", auth_key)` with no
+        // prefix), so leave `scheme` unset here rather than guessing one -
+        // `AuthConfig::header_value` already treats `None` as bare.
+        if let Some(providers) = value.get_mut("providers").and_then(|p| p.as_object_mut()) {
+            for provider in providers.values_mut() {
+                if let Some(provider) = provider.as_object_mut() {
+                    provider
+                        .entry("fallback_base_url")
+                        .or_insert(serde_json::Value::Null);
+                }
+            }
+        }
+    }
+
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("version".into(), CURRENT_VERSION.into());
+    }
+}
@@ -9,6 +9,8 @@ use tts_core::{
     structs::{GoogleGender, GoogleVoice, Result, TTSMode, WebhookConfig, WebhookConfigRaw},
 };
 
+use crate::{provider_config::ProviderDescriptor, voice_cache::RefreshingCache};
+
 pub async fn get_webhooks(
     http: &serenity::Http,
     webhooks_raw: WebhookConfigRaw,
@@ -32,13 +34,18 @@ pub async fn get_webhooks(
     })
 }
 
-async fn fetch_json<T>(reqwest: &reqwest::Client, url: reqwest::Url, auth_header: &str) -> Result<T>
+async fn fetch_json<T>(
+    reqwest: &reqwest::Client,
+    url: reqwest::Url,
+    auth_header: &str,
+    auth_value: &str,
+) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
     let resp = reqwest
         .get(url)
-        .header("Authorization", auth_header)
+        .header(auth_header, auth_value)
         .send()
         .await?
         .error_for_status()?
@@ -48,34 +55,198 @@ where
     Ok(resp)
 }
 
-pub async fn fetch_voices<T: serde::de::DeserializeOwned>(
+// Drives `fetch_json` off a provider descriptor's `base_url`, retrying
+// against `fallback_base_url` (if one is configured) when the primary
+// request fails instead of giving up outright.
+async fn fetch_json_with_failover<T>(
     reqwest: &reqwest::Client,
-    mut tts_service: reqwest::Url,
+    provider: &ProviderDescriptor,
+    path: &str,
+    extra_query: &[(&str, &str)],
+    auth_key: Option<&str>,
+) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let build_url = |base: &reqwest::Url| {
+        let mut url = base.clone();
+        url.set_path(path);
+        url.query_pairs_mut()
+            .extend_pairs(&provider.query)
+            .extend_pairs(extra_query)
+            .finish();
+        url
+    };
+
+    let auth_value = provider.auth.header_value(auth_key.unwrap_or(""));
+
+    match fetch_json(reqwest, build_url(&provider.base_url), &provider.auth.header, &auth_value).await {
+        Ok(value) => Ok(value),
+        Err(err) => match &provider.fallback_base_url {
+            Some(fallback_url) => {
+                eprintln!("Primary TTS endpoint failed ({err}), retrying against fallback");
+                fetch_json(reqwest, build_url(fallback_url), &provider.auth.header, &auth_value).await
+            }
+            None => Err(err),
+        },
+    }
+}
+
+// Canonicalises whitespace and splits `text` into fragments no longer than
+// `max_len`, so callers can synthesise each fragment separately against
+// engines that cap request length. Breaks on the last space before the cut
+// point, falling back to a hard cut at `max_len` when there's no space to
+// break on.
+fn split_for_tts(text: &str, max_len: usize) -> Vec<String> {
+    let normalised = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // A non-positive limit can't bound a window, so the slicing loop below
+    // would never advance; a misconfigured per-mode `max_len` shouldn't hang
+    // the bot, so just hand back the whole message unsplit.
+    if max_len == 0 || normalised.chars().count() <= max_len {
+        return vec![normalised];
+    }
+
+    let chars = normalised.chars().collect::<Vec<_>>();
+    let mut fragments = Vec::new();
+    let mut start = 0;
+
+    while chars.len() - start > max_len {
+        let window_end = start + max_len;
+        let break_at = chars[start..window_end]
+            .iter()
+            .rposition(|c| *c == ' ')
+            .map_or(window_end, |offset| start + offset);
+
+        fragments.push(chars[start..break_at].iter().collect());
+
+        start = break_at;
+        while chars.get(start) == Some(&' ') {
+            start += 1;
+        }
+    }
+
+    if start < chars.len() {
+        fragments.push(chars[start..].iter().collect());
+    }
+
+    fragments
+}
+
+async fn fetch_audio(
+    reqwest: &reqwest::Client,
+    provider: &ProviderDescriptor,
     auth_key: Option<&str>,
     mode: TTSMode,
-) -> Result<T> {
-    tts_service.set_path("voices");
+    text: &str,
+) -> Result<Vec<u8>> {
+    let mut tts_service = provider.base_url.clone();
+    tts_service.set_path("tts");
     tts_service
         .query_pairs_mut()
+        .extend_pairs(&provider.query)
         .append_pair("mode", mode.into())
-        .append_pair("raw", "true")
+        .append_pair("text", text)
+        // Headerless PCM, not WAV/OGG/MP3: `fetch_synthesis` concatenates
+        // fragments with a raw byte `extend`, which only produces a valid,
+        // gap-free stream when there's no per-fragment container framing to
+        // strip or re-stitch.
+        .append_pair("encoding", "pcm_s16le")
         .finish();
 
-    let res = fetch_json(reqwest, tts_service, auth_key.unwrap_or("")).await?;
+    let auth_value = provider.auth.header_value(auth_key.unwrap_or(""));
+    let resp = reqwest
+        .get(tts_service)
+        .header(&provider.auth.header, auth_value)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(resp.to_vec())
+}
+
+// Splits `text` into `max_len`-sized fragments (the limit differs by
+// `TTSMode`, so callers should pull it from their mode's config), synthesises
+// each fragment independently, and concatenates the resulting audio into one
+// stream for playback. `fetch_audio` requests headerless PCM specifically so
+// this concatenation is just a byte `extend` - it is NOT safe to point this
+// at an endpoint returning WAV/OGG/MP3, since each fragment would carry its
+// own header/framing and the result would be malformed or audibly gapped.
+pub async fn fetch_synthesis(
+    reqwest: &reqwest::Client,
+    provider: &ProviderDescriptor,
+    auth_key: Option<&str>,
+    mode: TTSMode,
+    text: &str,
+    max_len: usize,
+) -> Result<Vec<u8>> {
+    let mut audio = Vec::new();
+    for fragment in split_for_tts(text, max_len) {
+        let segment = fetch_audio(reqwest, provider, auth_key, mode, &fragment).await?;
+        audio.extend(segment);
+    }
+
+    Ok(audio)
+}
+
+pub async fn fetch_voices<T: serde::de::DeserializeOwned>(
+    reqwest: &reqwest::Client,
+    provider: &ProviderDescriptor,
+    auth_key: Option<&str>,
+    mode: TTSMode,
+) -> Result<T> {
+    let mode_str: &str = mode.into();
+    let res = fetch_json_with_failover(
+        reqwest,
+        provider,
+        &provider.voices_path,
+        &[("mode", mode_str), ("raw", "true")],
+        auth_key,
+    )
+    .await?;
 
     println!("Loaded voices for TTS Mode: {mode}");
     Ok(res)
 }
 
+// Wraps `fetch_voices` in a `RefreshingCache`: the voice map survives a
+// momentarily-down backend by falling back to `cache_path`, and is kept
+// fresh afterwards by refetching every `refresh_interval`.
+pub async fn fetch_voices_cached<T>(
+    reqwest: reqwest::Client,
+    provider: ProviderDescriptor,
+    auth_key: Option<String>,
+    mode: TTSMode,
+    cache_path: std::path::PathBuf,
+    refresh_interval: std::time::Duration,
+) -> Result<RefreshingCache<T>>
+where
+    T: Clone + Send + Sync + 'static + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let fetch = move || {
+        let reqwest = reqwest.clone();
+        let provider = provider.clone();
+        let auth_key = auth_key.clone();
+        async move { fetch_voices(&reqwest, &provider, auth_key.as_deref(), mode).await }
+    };
+
+    let cache = RefreshingCache::load(cache_path, &fetch).await?;
+    cache.spawn_refresh(refresh_interval, fetch);
+
+    Ok(cache)
+}
+
 pub async fn fetch_translation_languages(
     reqwest: &reqwest::Client,
-    mut tts_service: reqwest::Url,
+    provider: &ProviderDescriptor,
     auth_key: Option<&str>,
 ) -> Result<BTreeMap<FixedString<u8>, FixedString<u8>>> {
-    tts_service.set_path("translation_languages");
+    let languages_path = provider.translation_languages_path.as_deref().try_unwrap()?;
 
     let raw_langs: Vec<(String, FixedString<u8>)> =
-        fetch_json(reqwest, tts_service, auth_key.unwrap_or("")).await?;
+        fetch_json_with_failover(reqwest, provider, languages_path, &[], auth_key).await?;
 
     let lang_map = raw_langs.into_iter().map(|(mut lang, name)| {
         lang.make_ascii_lowercase();
@@ -86,58 +257,85 @@ pub async fn fetch_translation_languages(
     Ok(lang_map.collect())
 }
 
-// pub fn prepare_gcloud_voices(
-//     raw_map: Vec<GoogleVoice>,
-// ) -> BTreeMap<FixedString<u8>, BTreeMap<FixedString<u8>, GoogleGender>> {
-//     // {lang_accent: {variant: gender}}
-//     let mut cleaned_map = BTreeMap::new();
-//     for gvoice in raw_map {
-//         let variant = gvoice
-//             .name
-//             .splitn(3, '-')
-//             .nth(2)
-//             .and_then(|mode_variant| mode_variant.split_once('-'))
-//             .filter(|(mode, _)| *mode == "Standard")
-//             .map(|(_, variant)| variant);
-
-//         if let Some(variant) = variant {
-//             let [language] = gvoice.language_codes;
-//             cleaned_map
-//                 .entry(language)
-//                 .or_insert_with(BTreeMap::new)
-//                 .insert(FixedString::from_str_trunc(variant), gvoice.ssml_gender);
-//         }
-//     }
-
-//     cleaned_map
-// }
+// Wraps `fetch_translation_languages` in a `RefreshingCache`, the same way
+// `fetch_voices_cached` wraps `fetch_voices`.
+pub async fn fetch_translation_languages_cached(
+    reqwest: reqwest::Client,
+    provider: ProviderDescriptor,
+    auth_key: Option<String>,
+    cache_path: std::path::PathBuf,
+    refresh_interval: std::time::Duration,
+) -> Result<RefreshingCache<BTreeMap<FixedString<u8>, FixedString<u8>>>> {
+    let fetch = move || {
+        let reqwest = reqwest.clone();
+        let provider = provider.clone();
+        let auth_key = auth_key.clone();
+        async move { fetch_translation_languages(&reqwest, &provider, auth_key.as_deref()).await }
+    };
+
+    let cache = RefreshingCache::load(cache_path, &fetch).await?;
+    cache.spawn_refresh(refresh_interval, fetch);
+
+    Ok(cache)
+}
+
+// The quality/cost tier of a Google Cloud TTS voice, parsed from the
+// `language-REGION-Tier[-Variant]` shape of `GoogleVoice::name` (e.g.
+// `en-US-Neural2-F`, `en-US-Standard-A`). Ideally this would live alongside
+// `GoogleGender` in `tts_core::structs`, but it's defined here until that
+// crate picks it up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GoogleVoiceTier {
+    Standard,
+    Wavenet,
+    Neural2,
+    Studio,
+    Polyglot,
+    Chirp,
+    Other,
+}
+
+impl GoogleVoiceTier {
+    fn parse(tier: &str) -> Self {
+        match tier {
+            "Standard" => Self::Standard,
+            "Wavenet" => Self::Wavenet,
+            "Neural2" => Self::Neural2,
+            "Studio" => Self::Studio,
+            "Polyglot" => Self::Polyglot,
+            // Covers `Chirp`, `Chirp-HD` and newer generations like `Chirp3`
+            // (e.g. `en-US-Chirp3-HD-F`) - they're all the Chirp tier.
+            _ if tier.starts_with("Chirp") => Self::Chirp,
+            _ => Self::Other,
+        }
+    }
+}
 
 pub fn prepare_gcloud_voices(
     raw_map: Vec<GoogleVoice>,
-) -> BTreeMap<FixedString<u8>, BTreeMap<FixedString<u8>, GoogleGender>> {
-    // {lang_accent: {variant: gender}}
+) -> BTreeMap<FixedString<u8>, BTreeMap<GoogleVoiceTier, BTreeMap<FixedString<u8>, GoogleGender>>> {
+    // {lang_accent: {tier: {variant: gender}}}
     let mut cleaned_map = BTreeMap::new();
     for gvoice in raw_map {
-        // 1. 先取得語言碼之後的完整部分 (例如 "Standard-A" 或 "Wavenet-F")
-        if let Some(type_and_variant) = gvoice.name.splitn(3, '-').nth(2) {
-            
-            // 2. 判斷這部分是否為 "Standard-" 開頭
-            let final_variant =
-                if let Some(("Standard", variant_code)) = type_and_variant.split_once('-') {
-                    // 是 Standard，使用舊格式 -> "A"
-                    variant_code
-                } else {
-                    // 不是 Standard，使用新格式 -> "Wavenet-F"
-                    type_and_variant
-                };
-
-            // 3. 使用正確的 snake_case 欄位名稱來存入 map
-            let language = &gvoice.language_codes[0]; // 使用 language_codes
-            cleaned_map
-                .entry(FixedString::from_str_trunc(language))
-                .or_insert_with(BTreeMap::new)
-                .insert(FixedString::from_str_trunc(final_variant), gvoice.ssml_gender); // 使用 ssml_gender
-        }
+        let Some(tier_and_variant) = gvoice.name.splitn(3, '-').nth(2) else {
+            continue;
+        };
+
+        // A bare tier with no trailing variant letter (e.g. just `Chirp3`)
+        // has nothing after it to split on - use an empty variant rather
+        // than reusing the tier string itself as a bogus variant.
+        let (tier, variant) = match tier_and_variant.split_once('-') {
+            Some((tier, variant)) => (GoogleVoiceTier::parse(tier), variant),
+            None => (GoogleVoiceTier::parse(tier_and_variant), ""),
+        };
+
+        let [language] = gvoice.language_codes;
+        cleaned_map
+            .entry(language)
+            .or_insert_with(BTreeMap::new)
+            .entry(tier)
+            .or_insert_with(BTreeMap::new)
+            .insert(FixedString::from_str_trunc(variant), gvoice.ssml_gender);
     }
 
     cleaned_map
@@ -0,0 +1,120 @@
+// Streaming speech-to-text client, mirroring the voice-fetch layer in
+// `startup.rs`: the same per-provider auth-header plumbing as `fetch_json`,
+// gated behind a `TTSMode`-style enum so operators can choose or disable the
+// transcription backend.
+
+use std::collections::BTreeMap;
+
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt as _, Stream, StreamExt as _,
+};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest as _, http::HeaderName, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use tts_core::{opt_ext::OptionTryUnwrap as _, structs::Result};
+
+use crate::provider_config::AuthConfig;
+
+// Which transcription backend (if any) turns voice-channel audio back into
+// text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum STTMode {
+    Disabled,
+    Whisper,
+    Google,
+}
+
+// Where to connect for a given `STTMode`, mirroring `ProviderDescriptor`'s
+// base-url-plus-auth shape for the TTS side.
+pub struct SttEndpoint {
+    pub base_url: reqwest::Url,
+    pub auth: AuthConfig,
+}
+
+// One incremental result from an in-progress transcription session. A
+// `Partial` may still change as more audio arrives; a `Final` won't.
+#[derive(Debug, Clone)]
+pub enum TranscriptEvent {
+    Partial(String),
+    Final(String),
+}
+
+#[derive(serde::Deserialize)]
+struct RawTranscriptEvent {
+    is_final: bool,
+    text: String,
+}
+
+// The sending half of an open streaming transcription session. Feed it
+// audio frames with `send_audio` - split off from `SttTranscripts` so a
+// caller can keep feeding audio while concurrently awaiting transcripts on
+// the other half, instead of the two blocking each other.
+pub struct SttSession {
+    sink: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+}
+
+// The receiving half of an open streaming transcription session. Turn it
+// into a `Stream` with `into_stream` for partial and final hypotheses as the
+// backend produces them.
+pub struct SttTranscripts {
+    stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+}
+
+impl SttSession {
+    // Resolves `mode` against `endpoints` and opens a streaming connection,
+    // authenticating the same way `fetch_json` does for the TTS service.
+    // Returns `Ok(None)` for `STTMode::Disabled` instead of connecting.
+    pub async fn connect(
+        mode: STTMode,
+        endpoints: &BTreeMap<STTMode, SttEndpoint>,
+        auth_key: &str,
+    ) -> Result<Option<(Self, SttTranscripts)>> {
+        if mode == STTMode::Disabled {
+            return Ok(None);
+        }
+
+        let endpoint = endpoints.get(&mode).try_unwrap()?;
+
+        let mut request = endpoint.base_url.as_str().into_client_request()?;
+        request.headers_mut().insert(
+            HeaderName::from_bytes(endpoint.auth.header.as_bytes())?,
+            endpoint.auth.header_value(auth_key).parse()?,
+        );
+
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        let (sink, stream) = socket.split();
+
+        Ok(Some((Self { sink }, SttTranscripts { stream })))
+    }
+
+    // Feeds one frame of PCM/Opus audio into the in-progress session.
+    pub async fn send_audio(&mut self, frame: Vec<u8>) -> Result<()> {
+        self.sink.send(Message::Binary(frame)).await?;
+        Ok(())
+    }
+}
+
+impl SttTranscripts {
+    // Consumes the receiving half, yielding partial and final transcripts as
+    // the backend produces them rather than blocking for one final result.
+    pub fn into_stream(mut self) -> impl Stream<Item = Result<TranscriptEvent>> {
+        async_stream::try_stream! {
+            while let Some(message) = self.stream.next().await {
+                let Message::Text(text) = message? else {
+                    continue;
+                };
+
+                let raw: RawTranscriptEvent = serde_json::from_str(&text)?;
+                yield if raw.is_final {
+                    TranscriptEvent::Final(raw.text)
+                } else {
+                    TranscriptEvent::Partial(raw.text)
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::sync::RwLock;
+
+use tts_core::structs::Result;
+
+// Disk-backed cache for data that's otherwise only fetched once at startup
+// (voice maps, DeepL language maps, ...). Falls back to the last known good
+// copy on disk - logging a warning instead of aborting boot - if the live
+// fetch fails, and can be kept fresh afterwards via `spawn_refresh`.
+pub struct RefreshingCache<T> {
+    value: Arc<RwLock<T>>,
+    cache_path: PathBuf,
+}
+
+impl<T> RefreshingCache<T>
+where
+    T: Clone + Send + Sync + 'static + serde::Serialize + serde::de::DeserializeOwned,
+{
+    // Fetches fresh data via `fetch`, persisting it to `cache_path` on
+    // success. If `fetch` fails, logs a warning and loads the last known
+    // good value from `cache_path` instead of failing boot.
+    pub async fn load<F, Fut>(cache_path: PathBuf, fetch: F) -> Result<Self>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let value = match fetch().await {
+            Ok(value) => {
+                Self::persist(&cache_path, &value).await;
+                value
+            }
+            Err(err) => {
+                eprintln!(
+                    "Warning: live fetch failed ({err}), falling back to cache at {}",
+                    cache_path.display(),
+                );
+                let bytes = tokio::fs::read(&cache_path).await?;
+                serde_json::from_slice(&bytes)?
+            }
+        };
+
+        Ok(Self {
+            value: Arc::new(RwLock::new(value)),
+            cache_path,
+        })
+    }
+
+    pub fn handle(&self) -> Arc<RwLock<T>> {
+        Arc::clone(&self.value)
+    }
+
+    // Spawns a background task that refetches every `interval` and
+    // atomically swaps the refreshed value in, persisting it to disk.
+    pub fn spawn_refresh<F, Fut>(&self, interval: Duration, fetch: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<T>> + Send,
+    {
+        let value = Arc::clone(&self.value);
+        let cache_path = self.cache_path.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await; // first tick fires immediately; `load` just produced the current value
+
+            loop {
+                ticker.tick().await;
+                match fetch().await {
+                    Ok(fresh) => {
+                        Self::persist(&cache_path, &fresh).await;
+                        *value.write().await = fresh;
+                    }
+                    Err(err) => eprintln!("Warning: background refresh failed: {err}"),
+                }
+            }
+        });
+    }
+
+    // Writes to a sibling temp file and renames it into place, so a crash or
+    // a refresh racing a concurrent `load` never leaves `cache_path`
+    // truncated / half-written.
+    async fn persist(cache_path: &Path, value: &T) {
+        let json = match serde_json::to_vec(value) {
+            Ok(json) => json,
+            Err(err) => {
+                eprintln!("Warning: failed to serialise cache value: {err}");
+                return;
+            }
+        };
+
+        let tmp_path = cache_path.with_extension("tmp");
+        if let Err(err) = tokio::fs::write(&tmp_path, json).await {
+            eprintln!("Warning: failed to write cache at {}: {err}", tmp_path.display());
+            return;
+        }
+
+        if let Err(err) = tokio::fs::rename(&tmp_path, cache_path).await {
+            eprintln!("Warning: failed to commit cache at {}: {err}", cache_path.display());
+        }
+    }
+}